@@ -1,4 +1,4 @@
-#![feature(try_trait, alloc_layout_extra)]
+#![feature(try_trait, alloc_layout_extra, allocator_api, trusted_len)]
 #![forbid(missing_docs)]
 
 /*!
@@ -108,6 +108,12 @@ macro_rules! defer {
 /// fn to_bits_no_nans(v: Vec<f32>) -> Result<Vec<u32>, &'static str> {
 ///     try_zip_with!(v, |x| if x.is_nan() { Err("Found NaN!") } else { Ok(x.to_bits()) })
 /// }
+///
+/// // `Vec`, `Box<[T]>`, fixed-size arrays and `TrustedLen` iterators
+/// // (wrapped in `Iter`) can all appear in the same input tuple.
+/// fn sum_heterogeneous(v: Vec<i32>, b: Box<[i32]>) -> Vec<i32> {
+///    zip_with!((v, b, [1, 2, 3], vec_utils::Iter(40..43)), |v, b, a, i| v + b + a + i)
+/// }
 /// ```
 /// You can use as many input vectors as you want, just put them all inside the input tuple.
 /// Note that the second argument is not a closure, but syntax that looks like a closure,
@@ -122,6 +128,43 @@ macro_rules! defer {
 ///     zip_with!(a, move |a| a + b)
 /// }
 /// ```
+///
+/// Arrays and `Iter`-wrapped iterators never donate their own allocation, and
+/// in the example below no input's element layout matches `i32`, so the zip
+/// walks every input as an iterator. An early return still drops each element
+/// exactly once: the elements already consumed drop where the closure returns
+/// them, and the untouched tails drop with their iterators.
+///
+/// ```rust
+/// use std::cell::Cell;
+/// use vec_utils::{try_zip_with, Iter};
+///
+/// struct Counted<'a>(i32, &'a Cell<u32>);
+///
+/// impl Drop for Counted<'_> {
+///     fn drop(&mut self) {
+///         self.1.set(self.1.get() + 1);
+///     }
+/// }
+///
+/// let drops = Cell::new(0);
+/// let counted = |n: i32| Counted(n, &drops);
+///
+/// let v: Vec<Counted> = vec![counted(1), counted(2), counted(3), counted(4)];
+/// let b: Box<[Counted]> = vec![counted(10), counted(20), counted(30)].into_boxed_slice();
+/// let a: [Counted; 2] = [counted(100), counted(200)];
+/// let it = Iter(vec![counted(1000), counted(2000), counted(3000), counted(4000), counted(5000)].into_iter());
+/// let total: u32 = 4 + 3 + 2 + 5;
+///
+/// // the zip runs for `min(4, 3, 2, 5) == 2` steps; the second step errors
+/// // after already taking ownership of that step's elements
+/// let result: Result<Vec<i32>, &'static str> = try_zip_with!((v, b, a, it), |v, b, a, it| {
+///     if v.0 == 2 { Err("stop early") } else { Ok(v.0 + b.0 + a.0 + it.0) }
+/// });
+///
+/// assert_eq!(result, Err("stop early"));
+/// assert_eq!(drops.get(), total, "every element drops exactly once");
+/// ```
 #[macro_export]
 macro_rules! try_zip_with {
     ($vec:expr, $($move:ident)? |$($i:ident),+ $(,)?| $($work:tt)*) => {{
@@ -135,6 +178,35 @@ macro_rules! try_zip_with {
     }};
 }
 
+/// A fallible-allocation variant of [`try_zip_with`](macro.try_zip_with.html)
+///
+/// This behaves exactly like `try_zip_with`, but instead of aborting the
+/// process when a fresh output buffer cannot be allocated it returns an
+/// `AllocError`. The outer `Result` reports allocation failure and the inner
+/// `Result` reports the "closure"'s own error.
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use vec_utils::try_zip_with_alloc;
+/// use std::alloc::AllocError;
+///
+/// fn to_bits_no_nans(v: Vec<f32>) -> Result<Result<Vec<u32>, &'static str>, AllocError> {
+///     try_zip_with_alloc!(v, |x| if x.is_nan() { Err("Found NaN!") } else { Ok(x.to_bits()) })
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_zip_with_alloc {
+    ($vec:expr, $($move:ident)? |$($i:ident),+ $(,)?| $($work:tt)*) => {{
+        #[allow(unused_parens)]
+        let ($($i),*) = $vec;
+
+        $crate::fallible_zip_with_impl(
+            $crate::list!(WRAP $($i),*),
+            $($move)? |$crate::list!(PLACE $($i),*)| $($work)*
+        )
+    }};
+}
+
 /// A wrapper around `try_zip_with` for infallible mapping
 #[macro_export]
 macro_rules! zip_with {
@@ -149,6 +221,57 @@ macro_rules! zip_with {
     };
 }
 
+/// A filtering variant of [`try_zip_with`](macro.try_zip_with.html)
+///
+/// The "closure" yields `Result<Option<_>, E>` and only the `Some` values are
+/// kept. When an input's layout matches the output they are compacted into
+/// that input's reused allocation, so the resulting vector is logically
+/// shorter; otherwise they are collected into a fresh vector.
+///
+/// ```rust
+/// use vec_utils::try_filter_zip_with;
+///
+/// fn evens(v: Vec<i32>) -> Result<Vec<i32>, &'static str> {
+///     try_filter_zip_with!(v, |x| Ok(if x % 2 == 0 { Some(x) } else { None }))
+/// }
+/// ```
+#[macro_export]
+macro_rules! try_filter_zip_with {
+    ($vec:expr, $($move:ident)? |$($i:ident),+ $(,)?| $($work:tt)*) => {{
+        #[allow(unused_parens)]
+        let ($($i),*) = $vec;
+
+        $crate::try_filter_zip_with_impl(
+            $crate::list!(WRAP $($i),*),
+            $($move)? |$crate::list!(PLACE $($i),*)| $($work)*
+        )
+    }};
+}
+
+/// A wrapper around `try_filter_zip_with` for infallible filtering
+///
+/// The "closure" yields `Option<_>` and only the `Some` values are kept.
+///
+/// ```rust
+/// use vec_utils::filter_zip_with;
+///
+/// fn evens(v: Vec<i32>) -> Vec<i32> {
+///     filter_zip_with!(v, |x| if x % 2 == 0 { Some(x) } else { None })
+/// }
+/// ```
+#[macro_export]
+macro_rules! filter_zip_with {
+    ($vec:expr, $($move:ident)? |$($i:ident),+ $(,)?| $($work:tt)*) => {
+        match $crate::try_filter_zip_with!(
+            $vec, $($move)? |$($i),+|
+            Ok::<_, std::convert::Infallible>($($work)*)
+        ) {
+            Ok(x) => x,
+            Err(x) => match x {}
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! list {
@@ -2,7 +2,9 @@ use super::{Input, Output};
 
 pub use std::ops::Try;
 
-use std::alloc::Layout;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::iter::TrustedLen;
+use std::mem::ManuallyDrop;
 
 /// used by the `zip_with` macro
 #[doc(hidden)]
@@ -21,6 +23,20 @@ mod seal {
     pub trait Seal {}
 }
 
+/// Move an allocator out of a field by value.
+///
+/// The allocator carried by `Input`/`Output` has exactly one logical owner at a
+/// time. This is the single point at which that ownership is transferred: every
+/// caller guarantees — via the `drop_alloc`/`should_free_output` flags — that the
+/// source field is never read again after this call, so the bit-copy left behind
+/// by `ptr::read` is inert. This relies on neither `Input` nor `Output`
+/// implementing `Drop` over its `alloc` field; if either ever did, the allocator
+/// would be freed twice.
+#[inline(always)]
+unsafe fn take_alloc<A: Allocator>(alloc: &ManuallyDrop<A>) -> A {
+    std::ptr::read(&**alloc)
+}
+
 /// A specialized const-list for emulating varaidic generics
 ///
 /// To overload what elements can go in this tuple, please use the
@@ -38,6 +54,17 @@ pub unsafe trait Tuple: Seal {
     type Data;
     type Iter: Iterator<Item = Self::Item>;
 
+    /// The allocator backing the donated output buffer
+    ///
+    /// The output `Vec` has a single, statically-known allocator type, so every
+    /// input in the tuple must share one `Allocator` *type* — the `(A, T)` impl
+    /// enforces this with a `T: Tuple<Alloc = A::Alloc>` bound. Distinct
+    /// allocator *instances* are still fine: `max_cap` picks the donor at
+    /// runtime and its instance is what the output is freed through. Mixing
+    /// different allocator *types* in one zip is therefore not expressible and
+    /// is rejected at compile time.
+    type Alloc: Allocator;
+
     fn into_data(self) -> Self::Data;
 
     fn remaining_len(&self) -> usize;
@@ -48,9 +75,9 @@ pub unsafe trait Tuple: Seal {
 
     fn max_cap<V>(data: &Self::Data, depth: &mut u64) -> Option<usize>;
 
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V>;
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc>;
 
-    unsafe fn take_output_impl<V>(_: &mut Self::Data, min_cap: u64) -> Output<V>;
+    unsafe fn take_output_impl<V>(_: &mut Self::Data, min_cap: u64) -> Output<V, Self::Alloc>;
 
     unsafe fn next_unchecked(data: &mut Self::Data) -> Self::Item;
 
@@ -77,6 +104,13 @@ pub unsafe trait TupleElem {
     /// An iterator over the items in the collection
     type Iter: Iterator<Item = Self::Item>;
 
+    /// The allocator that owns this element's data-segment
+    ///
+    /// When this element donates its allocation (see `take_output`) the
+    /// resulting `Output` takes ownership of this allocator so that the
+    /// output `Vec` is freed through the same allocator it was carved from.
+    type Alloc: Allocator;
+
     /// The capacity of the data-segment
     fn capacity(data: &Self::Data) -> usize;
 
@@ -95,9 +129,10 @@ pub unsafe trait TupleElem {
     fn check_layout<V>() -> bool;
 
     /// Try and create a new output data-segment, if the output segment
-    /// is created, then it owns it's allocation. So you must not deallocate
-    /// the allocation backing `Output<V>`
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V>;
+    /// is created, then it owns it's allocation *and* the allocator that
+    /// backs it. So you must not deallocate the allocation backing
+    /// `Output<V, Self::Alloc>`, nor reuse `Self::Alloc` afterwards.
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc>;
 
     /// Get the next_unchecked element
     ///
@@ -119,6 +154,7 @@ unsafe impl<A: TupleElem> TupleElem for (A,) {
     type Item = A::Item;
     type Data = A::Data;
     type Iter = A::Iter;
+    type Alloc = A::Alloc;
 
     #[inline(always)]
     fn capacity(data: &Self::Data) -> usize {
@@ -146,7 +182,7 @@ unsafe impl<A: TupleElem> TupleElem for (A,) {
     }
 
     #[inline]
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V> {
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc> {
         A::take_output(data)
     }
 
@@ -161,10 +197,11 @@ unsafe impl<A: TupleElem> TupleElem for (A,) {
     }
 }
 
-unsafe impl<A> TupleElem for Vec<A> {
+unsafe impl<A, Alloc: Allocator> TupleElem for Vec<A, Alloc> {
     type Item = A;
-    type Data = Input<A>;
-    type Iter = std::vec::IntoIter<A>;
+    type Data = Input<A, Alloc>;
+    type Iter = std::vec::IntoIter<A, Alloc>;
+    type Alloc = Alloc;
 
     #[inline(always)]
     fn capacity(data: &Self::Data) -> usize {
@@ -192,11 +229,77 @@ unsafe impl<A> TupleElem for Vec<A> {
     }
 
     #[inline]
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V> {
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc> {
+        debug_assert!(Layout::new::<A>() == Layout::new::<V>());
+
+        // hand the allocation *and* its allocator over to the `Output`; the
+        // `Input` must no longer free either of them
+        data.drop_alloc = false;
+        let alloc = take_alloc(&data.alloc);
+        Output::new(data.start as *mut V, data.cap, alloc)
+    }
+
+    #[inline]
+    unsafe fn next_unchecked(data: &mut Self::Data) -> Self::Item {
+        let ptr = data.ptr;
+        data.ptr = data.ptr.add(1);
+        ptr.read()
+    }
+
+    #[inline]
+    unsafe fn drop_rest(data: &mut Self::Data, len: usize) {
+        defer! {
+            if data.drop_alloc {
+                Vec::from_raw_parts_in(data.start, 0, data.cap, take_alloc(&data.alloc));
+            }
+        }
+
+        std::ptr::drop_in_place(std::slice::from_raw_parts_mut(data.ptr, data.len - len));
+    }
+}
+
+// A boxed slice's capacity always equals its length, so it can donate its
+// allocation exactly like a `Vec` whose length is its capacity. We route
+// through `Vec`'s `Input` so the reuse, drop and dealloc logic is shared.
+unsafe impl<A> TupleElem for Box<[A]> {
+    type Item = A;
+    type Data = Input<A>;
+    type Iter = std::vec::IntoIter<A>;
+    type Alloc = Global;
+
+    #[inline(always)]
+    fn capacity(data: &Self::Data) -> usize {
+        data.cap
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    #[inline]
+    fn into_data(self) -> Self::Data {
+        // `Vec::from` on a boxed slice reuses the allocation (`cap == len`)
+        Input::from(Vec::from(self))
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::Iter {
+        IntoIterator::into_iter(Vec::from(self))
+    }
+
+    #[inline]
+    fn check_layout<V>() -> bool {
+        Layout::new::<A>() == Layout::new::<V>()
+    }
+
+    #[inline]
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc> {
         debug_assert!(Layout::new::<A>() == Layout::new::<V>());
-        
+
         data.drop_alloc = false;
-        Output::new(data.start as *mut V, data.cap)
+        let alloc = take_alloc(&data.alloc);
+        Output::new(data.start as *mut V, data.cap, alloc)
     }
 
     #[inline]
@@ -210,7 +313,7 @@ unsafe impl<A> TupleElem for Vec<A> {
     unsafe fn drop_rest(data: &mut Self::Data, len: usize) {
         defer! {
             if data.drop_alloc {
-                Vec::from_raw_parts(data.start, 0, data.cap);
+                Vec::from_raw_parts_in(data.start, 0, data.cap, take_alloc(&data.alloc));
             }
         }
 
@@ -218,6 +321,134 @@ unsafe impl<A> TupleElem for Vec<A> {
     }
 }
 
+/// A fixed-size array walked in place from a stack buffer.
+///
+/// It never donates an output allocation (`check_layout` is always `false`),
+/// so it can only ride along with another input that does.
+pub struct ArrayData<A, const N: usize> {
+    data: ManuallyDrop<[A; N]>,
+    // the number of elements already yielded by `next_unchecked`
+    index: usize,
+}
+
+unsafe impl<A, const N: usize> TupleElem for [A; N] {
+    type Item = A;
+    type Data = ArrayData<A, N>;
+    type Iter = std::array::IntoIter<A, N>;
+    type Alloc = Global;
+
+    #[inline(always)]
+    fn capacity(_data: &Self::Data) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn into_data(self) -> Self::Data {
+        ArrayData {
+            data: ManuallyDrop::new(self),
+            index: 0,
+        }
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::Iter {
+        IntoIterator::into_iter(self)
+    }
+
+    #[inline]
+    fn check_layout<V>() -> bool {
+        // a stack array owns no heap allocation to donate
+        false
+    }
+
+    #[inline]
+    unsafe fn take_output<V>(_data: &mut Self::Data) -> Output<V, Self::Alloc> {
+        // `check_layout` is always `false`, so `max_cap` never selects an array
+        // as the donor and this is never reached
+        unreachable!("an array never donates its allocation")
+    }
+
+    #[inline]
+    unsafe fn next_unchecked(data: &mut Self::Data) -> Self::Item {
+        let ptr = data.data.as_ptr().add(data.index);
+        data.index += 1;
+        ptr.read()
+    }
+
+    #[inline]
+    unsafe fn drop_rest(data: &mut Self::Data, len: usize) {
+        // the stack buffer owns no allocation; just drop the unconsumed tail
+        let ptr = data.data.as_mut_ptr().add(len);
+        std::ptr::drop_in_place(std::slice::from_raw_parts_mut(ptr, N - len));
+    }
+}
+
+/// A `TrustedLen` iterator adapter usable as a zip input.
+///
+/// Wrap any `Iterator + TrustedLen` in `Iter` to contribute its known length to
+/// the zip. It never donates an allocation, so it always falls back to iterator
+/// collection.
+pub struct Iter<I>(pub I);
+
+unsafe impl<I: Iterator + TrustedLen> TupleElem for Iter<I> {
+    type Item = I::Item;
+    type Data = I;
+    type Iter = I;
+    type Alloc = Global;
+
+    #[inline(always)]
+    fn capacity(data: &Self::Data) -> usize {
+        data.size_hint().0
+    }
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        // `TrustedLen` guarantees the lower bound is exact
+        self.0.size_hint().0
+    }
+
+    #[inline]
+    fn into_data(self) -> Self::Data {
+        self.0
+    }
+
+    #[inline]
+    fn into_iter(self) -> Self::Iter {
+        self.0
+    }
+
+    #[inline]
+    fn check_layout<V>() -> bool {
+        // an iterator has no backing allocation to reuse
+        false
+    }
+
+    #[inline]
+    unsafe fn take_output<V>(_data: &mut Self::Data) -> Output<V, Self::Alloc> {
+        // `check_layout` is always `false`, so `max_cap` never selects an
+        // iterator as the donor and this is never reached
+        unreachable!("an iterator never donates its allocation")
+    }
+
+    #[inline]
+    unsafe fn next_unchecked(data: &mut Self::Data) -> Self::Item {
+        data.next().unwrap_unchecked()
+    }
+
+    #[inline]
+    unsafe fn drop_rest(data: &mut Self::Data, _len: usize) {
+        // dropping the wrapped iterator drops the unconsumed items exactly once;
+        // `data` itself is dropped as a field of `ZipWithIter` afterwards, so we
+        // must not drop the tail here
+        let _ = data;
+    }
+}
+
 impl<A: TupleElem> Seal for (A,) {}
 unsafe impl<A: TupleElem> Tuple for (A,) {
     const LEN: u64 = 0;
@@ -225,6 +456,7 @@ unsafe impl<A: TupleElem> Tuple for (A,) {
     type Item = A::Item;
     type Data = A::Data;
     type Iter = A::Iter;
+    type Alloc = A::Alloc;
 
     #[inline]
     fn into_data(self) -> Self::Data {
@@ -257,12 +489,12 @@ unsafe impl<A: TupleElem> Tuple for (A,) {
     }
 
     #[inline]
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V> {
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc> {
         A::take_output::<V>(data)
     }
 
     #[inline]
-    unsafe fn take_output_impl<V>(data: &mut Self::Data, depth: u64) -> Output<V> {
+    unsafe fn take_output_impl<V>(data: &mut Self::Data, depth: u64) -> Output<V, Self::Alloc> {
         debug_assert_eq!(Self::LEN, depth);
         A::take_output(data)
     }
@@ -278,14 +510,21 @@ unsafe impl<A: TupleElem> Tuple for (A,) {
     }
 }
 
-impl<A: TupleElem, T: Tuple> Seal for (A, T) {}
-unsafe impl<A: TupleElem, T: Tuple> Tuple for (A, T) {
+impl<A: TupleElem, T: Tuple<Alloc = A::Alloc>> Seal for (A, T) {}
+unsafe impl<A: TupleElem, T: Tuple<Alloc = A::Alloc>> Tuple for (A, T) {
     const LEN: u64 = T::LEN + 1;
-    
+
     type Item = (A::Item, T::Item);
     type Data = (A::Data, T::Data);
     type Iter = std::iter::Zip<A::Iter, T::Iter>;
 
+    // The output has one statically-known allocator type, so the whole tuple
+    // must agree on it — hence the `T: Tuple<Alloc = A::Alloc>` bound on this
+    // impl. The head element names the shared type; the donor chosen by
+    // `max_cap` at runtime supplies the concrete instance the output is freed
+    // through. A zip mixing *different* allocator types is not representable.
+    type Alloc = A::Alloc;
+
     #[inline]
     fn into_data(self) -> Self::Data {
         (self.0.into_data(), self.1.into_data())
@@ -327,7 +566,7 @@ unsafe impl<A: TupleElem, T: Tuple> Tuple for (A, T) {
     }
 
     #[inline]
-    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V> {
+    unsafe fn take_output<V>(data: &mut Self::Data) -> Output<V, Self::Alloc> {
         let mut depth = 0;
         let val = Self::max_cap::<V>(data, &mut depth);
         debug_assert!(val.is_some());
@@ -335,7 +574,7 @@ unsafe impl<A: TupleElem, T: Tuple> Tuple for (A, T) {
     }
 
     #[inline]
-    unsafe fn take_output_impl<V>((a, rest): &mut Self::Data, depth: u64) -> Output<V> {
+    unsafe fn take_output_impl<V>((a, rest): &mut Self::Data, depth: u64) -> Output<V, Self::Alloc> {
         if Self::LEN == depth {
             A::take_output(a)
         } else {
@@ -360,8 +599,9 @@ unsafe impl<A: TupleElem, T: Tuple> Tuple for (A, T) {
 
 struct ZipWithIter<V, In: Tuple> {
     // This left buffer is the one that will be reused
-    // to write the output into
-    output: Output<V>,
+    // to write the output into. It carries the allocator of the donor input
+    // so the finished `Vec` is freed through the same allocator.
+    output: Output<V, In::Alloc>,
 
     // We will only read from this buffer
     input: In::Data,
@@ -372,15 +612,117 @@ struct ZipWithIter<V, In: Tuple> {
     // the remaing length of the input
     remaining_len: usize,
 
+    // the number of outputs actually written into `output`
+    //
+    // for a plain zip this tracks the read cursor exactly, but a filtering zip
+    // only advances the write cursor on `Some`, so it can lag behind the number
+    // of consumed inputs. `Drop` drops exactly this many produced outputs.
+    written_len: usize,
+
     should_free_output: bool,
 }
 
-/// Does the work of the `try_zip_with` or `zip_with` macros.
-pub fn try_zip_with<R: Try, In: Tuple>(
+/// Does the work of the `try_zip_with` or `zip_with` macros, using `alloc` for
+/// the output buffer when no input can donate one.
+///
+/// The reuse fast path never touches `alloc` (the donor supplies the output
+/// allocator), so this works for allocators that are not `Default` — exactly
+/// the arena/bump/kernel allocators the allocator support exists to unlock.
+///
+/// On the reuse fast path the donor's own allocator *instance* backs the
+/// output, not a fresh one built from `alloc`:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use std::alloc::{AllocError, Allocator, Global, Layout};
+/// use std::convert::Infallible;
+/// use std::ptr::NonNull;
+/// use vec_utils::try_zip_with_in;
+///
+/// // A distinct allocator *type* from `Global`, so a returned `Tagged(7)`
+/// // can only have come from the donor, not from a freshly defaulted one.
+/// #[derive(Clone, Copy)]
+/// struct Tagged(u8);
+///
+/// unsafe impl Allocator for Tagged {
+///     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+///         Global.allocate(layout)
+///     }
+///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+///         Global.deallocate(ptr, layout)
+///     }
+/// }
+///
+/// let mut donor = Vec::new_in(Tagged(7));
+/// donor.extend([1, 2, 3]);
+///
+/// // `Tagged(0)` is only the *fallback* allocator for when no input can
+/// // donate its buffer; since `i32 -> i32` reuses `donor`'s own buffer, the
+/// // output carries `donor`'s `Tagged(7)` instance instead.
+/// let out = try_zip_with_in((donor,), Tagged(0), |x: i32| Ok::<_, Infallible>(x + 1)).unwrap();
+///
+/// assert_eq!(out.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+/// assert_eq!(out.allocator().0, 7);
+/// ```
+///
+/// A passenger that can't donate (its layout doesn't match the output, or
+/// another input was picked as the donor) is still freed through its *own*
+/// allocator instance, never the donor's:
+///
+/// ```rust
+/// # #![feature(allocator_api)]
+/// use std::alloc::{AllocError, Allocator, Global, Layout};
+/// use std::cell::Cell;
+/// use std::convert::Infallible;
+/// use std::ptr::NonNull;
+/// use vec_utils::try_zip_with_in;
+///
+/// // Counts how many times *this instance* frees a buffer, so the donor
+/// // (never freed — its buffer becomes the output) can be told apart from a
+/// // passenger that must free its own backing allocation via `drop_rest`.
+/// #[derive(Clone, Copy)]
+/// struct Counting<'a>(&'a Cell<u32>);
+///
+/// unsafe impl Allocator for Counting<'_> {
+///     fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+///         Global.allocate(layout)
+///     }
+///     unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+///         self.0.set(self.0.get() + 1);
+///         Global.deallocate(ptr, layout)
+///     }
+/// }
+///
+/// let donor_frees = Cell::new(0);
+/// let passenger_frees = Cell::new(0);
+///
+/// let mut donor = Vec::new_in(Counting(&donor_frees));
+/// donor.extend([1i64, 2, 3]);
+/// let mut passenger = Vec::new_in(Counting(&passenger_frees));
+/// passenger.extend([10u8, 20, 30]);
+///
+/// // `i64` matches the donor's own element layout, so it donates its
+/// // buffer; `u8` can't reuse it and must free its own allocation.
+/// let out = try_zip_with_in(
+///     (donor, (passenger,)),
+///     Counting(&donor_frees),
+///     |(a, b): (i64, u8)| Ok::<_, Infallible>(a + i64::from(b)),
+/// )
+/// .unwrap();
+///
+/// assert_eq!(out.iter().copied().collect::<Vec<_>>(), vec![11, 22, 33]);
+/// assert_eq!(donor_frees.get(), 0, "the donor's buffer was reused, not freed");
+/// assert_eq!(passenger_frees.get(), 1, "the passenger freed its own allocation exactly once");
+/// ```
+pub fn try_zip_with_in<R: Try, In: Tuple>(
     input: In,
+    alloc: In::Alloc,
     f: impl FnMut(In::Item) -> R,
-) -> Result<Vec<R::Ok>, R::Error> {
+) -> Result<Vec<R::Ok, In::Alloc>, R::Error> {
     if In::check_layout::<R::Ok>() {
+        // the donor supplies the output allocator on the reuse fast path
+        drop(alloc);
+
         let len = input.remaining_len();
         let mut input = input.into_data();
 
@@ -391,19 +733,160 @@ pub fn try_zip_with<R: Try, In: Tuple>(
             input,
             initial_len: len,
             remaining_len: len,
+            written_len: 0,
             should_free_output: true,
         }
         .try_into_vec(f)
     } else {
-        input.into_iter().map(f).map(R::into_result).collect()
+        // no input can donate its buffer, so allocate a fresh one through the
+        // caller-provided allocator
+        let mut f = f;
+        let mut out = Vec::new_in(alloc);
+        for item in input.into_iter() {
+            out.push(f(item).into_result()?);
+        }
+        Ok(out)
+    }
+}
+
+/// Does the work of the `try_zip_with` or `zip_with` macros.
+///
+/// A convenience wrapper around [`try_zip_with_in`] that default-constructs the
+/// fallback allocator; use `try_zip_with_in` directly for allocators that are
+/// not `Default`.
+pub fn try_zip_with<R: Try, In: Tuple>(
+    input: In,
+    f: impl FnMut(In::Item) -> R,
+) -> Result<Vec<R::Ok, In::Alloc>, R::Error>
+where
+    In::Alloc: Default,
+{
+    try_zip_with_in(input, In::Alloc::default(), f)
+}
+
+/// Does the work of the `try_zip_with_alloc` macro.
+///
+/// This is the fallible-allocation twin of [`try_zip_with`]: it never aborts on
+/// allocation failure. The outer `Result` carries an [`AllocError`] raised while
+/// securing the output buffer, the inner `Result` carries the closure's own
+/// `Try::Error`.
+pub fn fallible_zip_with_in<R: Try, In: Tuple>(
+    input: In,
+    alloc: In::Alloc,
+    f: impl FnMut(In::Item) -> R,
+) -> Result<Result<Vec<R::Ok, In::Alloc>, R::Error>, AllocError> {
+    if In::check_layout::<R::Ok>() {
+        drop(alloc);
+
+        let len = input.remaining_len();
+        let mut input = input.into_data();
+
+        // reusing an input allocation is infallible: the donor's buffer is
+        // already large enough for `R::Ok`, so no allocation happens here
+        let output = unsafe { In::take_output::<R::Ok>(&mut input) };
+
+        Ok(ZipWithIter::<_, In> {
+            output,
+            input,
+            initial_len: len,
+            remaining_len: len,
+            written_len: 0,
+            should_free_output: true,
+        }
+        .try_into_vec(f))
+    } else {
+        // no input can donate its buffer, so grow a fresh one fallibly through
+        // the caller-provided allocator
+        let mut f = f;
+        let mut out = Vec::new_in(alloc);
+        for item in input.into_iter() {
+            out.try_reserve(1).map_err(|_| AllocError)?;
+            match f(item).into_result() {
+                Ok(v) => out.push(v),
+                Err(e) => return Ok(Err(e)),
+            }
+        }
+        Ok(Ok(out))
     }
 }
 
+/// A convenience wrapper around [`fallible_zip_with_in`] that
+/// default-constructs the fallback allocator.
+///
+/// This is the entry point behind the `try_zip_with_alloc!` macro.
+pub fn fallible_zip_with_impl<R: Try, In: Tuple>(
+    input: In,
+    f: impl FnMut(In::Item) -> R,
+) -> Result<Result<Vec<R::Ok, In::Alloc>, R::Error>, AllocError>
+where
+    In::Alloc: Default,
+{
+    fallible_zip_with_in(input, In::Alloc::default(), f)
+}
+
+/// Does the work of the `filter_zip_with` or `try_filter_zip_with` macros.
+///
+/// Like [`try_zip_with`], but the closure yields `Option<R::Ok>` and only
+/// `Some` values are kept. When an input's layout matches `V`, the surviving
+/// values are compacted into that input's reused allocation, so the returned
+/// `Vec` is logically shorter than the input while sharing its buffer;
+/// otherwise they are pushed into a fresh `Vec` allocated through `alloc`.
+pub fn try_filter_zip_with_in<V, R: Try<Ok = Option<V>>, In: Tuple>(
+    input: In,
+    alloc: In::Alloc,
+    f: impl FnMut(In::Item) -> R,
+) -> Result<Vec<V, In::Alloc>, R::Error> {
+    if In::check_layout::<V>() {
+        drop(alloc);
+
+        let len = input.remaining_len();
+        let mut input = input.into_data();
+
+        ZipWithIter::<_, In> {
+            output: unsafe {
+                In::take_output::<V>(&mut input)
+            },
+            input,
+            initial_len: len,
+            remaining_len: len,
+            written_len: 0,
+            should_free_output: true,
+        }
+        .try_into_filtered_vec(f)
+    } else {
+        // no input can donate its buffer, so allocate a fresh one through the
+        // caller-provided allocator and only push the survivors
+        let mut f = f;
+        let mut out = Vec::new_in(alloc);
+        for item in input.into_iter() {
+            if let Some(v) = f(item).into_result()? {
+                out.push(v);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// A convenience wrapper around [`try_filter_zip_with_in`] that
+/// default-constructs the fallback allocator.
+///
+/// This is the entry point behind the `filter_zip_with!` and
+/// `try_filter_zip_with!` macros.
+pub fn try_filter_zip_with_impl<V, R: Try<Ok = Option<V>>, In: Tuple>(
+    input: In,
+    f: impl FnMut(In::Item) -> R,
+) -> Result<Vec<V, In::Alloc>, R::Error>
+where
+    In::Alloc: Default,
+{
+    try_filter_zip_with_in(input, In::Alloc::default(), f)
+}
+
 impl<V, In: Tuple> ZipWithIter<V, In> {
     pub fn try_into_vec<R: Try<Ok = V>, F: FnMut(In::Item) -> R>(
         mut self,
         mut f: F,
-    ) -> Result<Vec<V>, R::Error> {
+    ) -> Result<Vec<V, In::Alloc>, R::Error> {
         // this does a pointer walk and reads from left and right in lock-step
         // then passes those values to the function to be processed
         unsafe {
@@ -414,6 +897,7 @@ impl<V, In: Tuple> ZipWithIter<V, In> {
 
                 self.output.ptr.write(f(input)?);
                 self.output.ptr = self.output.ptr.add(1);
+                self.written_len += 1;
             }
         }
 
@@ -423,10 +907,53 @@ impl<V, In: Tuple> ZipWithIter<V, In> {
 
         unsafe {
             // create the vector now, so that if we panic in drop, we don't leak it
-            Ok(Vec::from_raw_parts(
+            Ok(Vec::from_raw_parts_in(
                 self.output.start as *mut V,
                 self.initial_len,
                 self.output.cap,
+                take_alloc(&self.output.alloc),
+            ))
+        }
+    }
+
+    /// The filtering counterpart of [`try_into_vec`](Self::try_into_vec).
+    ///
+    /// The read cursor walks the reused buffer via `In::next_unchecked` exactly
+    /// as before, but a separate write cursor (`output.ptr`) only advances when
+    /// the closure yields `Some`. The resulting `Vec`'s length is the number of
+    /// written survivors, not the initial input length, so the old allocation
+    /// is reused while the logical vector shrinks.
+    pub fn try_into_filtered_vec<R: Try<Ok = Option<V>>, F: FnMut(In::Item) -> R>(
+        mut self,
+        mut f: F,
+    ) -> Result<Vec<V, In::Alloc>, R::Error> {
+        unsafe {
+            while let Some(remaining_len) = self.remaining_len.checked_sub(1) {
+                self.remaining_len = remaining_len;
+
+                let input = In::next_unchecked(&mut self.input);
+
+                // only the write cursor is conditional; the read cursor above
+                // always advances so `Drop` can reclaim the untouched tail
+                if let Some(output) = f(input)? {
+                    self.output.ptr.write(output);
+                    self.output.ptr = self.output.ptr.add(1);
+                    self.written_len += 1;
+                }
+            }
+        }
+
+        // We don't want to drop `self` if dropping the excess elements panics
+        // as that could lead to double drops
+        self.should_free_output = false;
+
+        unsafe {
+            // the logical length is the number of survivors, reusing the buffer
+            Ok(Vec::from_raw_parts_in(
+                self.output.start as *mut V,
+                self.written_len,
+                self.output.cap,
+                take_alloc(&self.output.alloc),
             ))
         }
     }
@@ -440,6 +967,7 @@ impl<V, In: Tuple> Drop for ZipWithIter<V, In> {
             should_free_output,
             initial_len,
             remaining_len,
+            written_len,
             ..
         } = self;
 
@@ -448,7 +976,16 @@ impl<V, In: Tuple> Drop for ZipWithIter<V, In> {
         defer! {
             if should_free_output {
                 unsafe {
-                    Vec::from_raw_parts(output.start, initialized_len - 1, output.cap);
+                    // drop exactly the produced outputs in `[start, write_cursor)`
+                    // and free the reused allocation once. `written_len` (not the
+                    // read cursor) is the write cursor, so this stays correct even
+                    // when a filtering zip dropped some inputs without writing.
+                    Vec::from_raw_parts_in(
+                        output.start,
+                        written_len,
+                        output.cap,
+                        take_alloc(&output.alloc),
+                    );
                 }
             }
         }
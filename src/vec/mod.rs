@@ -0,0 +1,92 @@
+use std::alloc::{Allocator, Global};
+use std::mem::ManuallyDrop;
+
+mod general_zip;
+
+pub use self::general_zip::*;
+pub use self::general_zip::try_zip_with as try_zip_with_impl;
+
+/// The raw data-segment of an input buffer that a zip can walk and, if its
+/// layout matches the output element, donate to the result.
+///
+/// The allocator is held in a `ManuallyDrop` because `Input` carries no `Drop`
+/// impl: its allocation is reclaimed explicitly — either by `drop_rest` when the
+/// buffer is *not* reused, or by the `Output` it is handed to when it is. The
+/// `drop_alloc` flag records which of those two owners is responsible, so the
+/// allocator is moved out (via `take_alloc`) exactly once and never freed twice.
+///
+/// This is `pub` (rather than `pub(crate)`) solely because it appears as the
+/// `Tuple`/`TupleElem` associated `Data` type and in `take_output`'s return
+/// type; its fields remain `pub(crate)` and it is not meant to be constructed
+/// or inspected outside this crate.
+pub struct Input<T, A: Allocator = Global> {
+    /// the start of the allocation, used to reconstruct / free it
+    pub(crate) start: *mut T,
+    /// the read cursor, advanced as elements are consumed
+    pub(crate) ptr: *mut T,
+    /// the number of initialized elements
+    pub(crate) len: usize,
+    /// the capacity of the allocation
+    pub(crate) cap: usize,
+    /// whether this `Input` still owns (and must free) the allocation
+    pub(crate) drop_alloc: bool,
+    /// the allocator backing the allocation
+    pub(crate) alloc: ManuallyDrop<A>,
+}
+
+impl<T, A: Allocator> From<Vec<T, A>> for Input<T, A> {
+    #[inline]
+    fn from(vec: Vec<T, A>) -> Self {
+        // decompose the `Vec` without running its destructor so the allocation
+        // and allocator are taken over wholesale
+        let mut vec = ManuallyDrop::new(vec);
+        let start = vec.as_mut_ptr();
+        let len = vec.len();
+        let cap = vec.capacity();
+        let alloc = unsafe { std::ptr::read(vec.allocator()) };
+
+        Input {
+            start,
+            ptr: start,
+            len,
+            cap,
+            drop_alloc: true,
+            alloc: ManuallyDrop::new(alloc),
+        }
+    }
+}
+
+/// The raw data-segment the zip writes survivors into and finally reconstitutes
+/// into the output `Vec`.
+///
+/// Like [`Input`], the allocator lives in a `ManuallyDrop` and `Output` has no
+/// `Drop` impl: ownership is surrendered exactly once, when `try_into_vec` (or
+/// its filtering counterpart) rebuilds the `Vec`, or by the `ZipWithIter` drop
+/// glue if the walk panics or the closure returns an error.
+///
+/// `pub` for the same reason as [`Input`]: it leaks through `take_output`'s
+/// return type on the public `TupleElem` trait. Fields stay `pub(crate)`.
+pub struct Output<V, A: Allocator = Global> {
+    /// the start of the allocation the output is built in
+    pub(crate) start: *mut V,
+    /// the write cursor, advanced as survivors are written
+    pub(crate) ptr: *mut V,
+    /// the capacity of the allocation
+    pub(crate) cap: usize,
+    /// the allocator backing the allocation
+    pub(crate) alloc: ManuallyDrop<A>,
+}
+
+impl<V, A: Allocator> Output<V, A> {
+    /// Wrap a freshly acquired (start, cap, allocator) triple, seating the write
+    /// cursor at the start of the buffer.
+    #[inline]
+    pub(crate) fn new(start: *mut V, cap: usize, alloc: A) -> Self {
+        Output {
+            start,
+            ptr: start,
+            cap,
+            alloc: ManuallyDrop::new(alloc),
+        }
+    }
+}